@@ -0,0 +1,328 @@
+// Hardware-accelerated SHA-256 compression, selected at runtime (x86-64)
+// or compile time (AArch64), falling back to the scalar `transform` in
+// lib.rs. Bit-identical to the scalar loop; the FFI surface is unchanged.
+
+use crate::K;
+
+#[cfg(target_arch = "x86_64")]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(target_arch = "x86_64")]
+static SHA_NI_STATE: AtomicU8 = AtomicU8::new(0); // 0 = unknown, 1 = no, 2 = yes
+
+// Runtime CPUID check for the SHA extension (CPUID.(EAX=7,ECX=0):EBX[29]).
+// `no_std` has no `is_x86_feature_detected!` (that macro lives in `std`),
+// so the check is done directly with `__cpuid_count`, cached after first use.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn sha_ni_available() -> bool {
+    match SHA_NI_STATE.load(Ordering::Relaxed) {
+        1 => return false,
+        2 => return true,
+        _ => {}
+    }
+
+    use core::arch::x86_64::__cpuid_count;
+    let leaf7 = __cpuid_count(7, 0);
+    let supported = (leaf7.ebx & (1 << 29)) != 0;
+
+    SHA_NI_STATE.store(if supported { 2 } else { 1 }, Ordering::Relaxed);
+    supported
+}
+
+// Packs 4 consecutive round constants `K[i..i+4]` into one 128-bit vector,
+// matching the message-schedule vectors (`msg0`..`msg3`) they're added to.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+unsafe fn k_quad(i: usize) -> core::arch::x86_64::__m128i {
+    core::arch::x86_64::_mm_set_epi32(
+        K[i + 3] as i32,
+        K[i + 2] as i32,
+        K[i + 1] as i32,
+        K[i] as i32,
+    )
+}
+
+// Processes one 64-byte block with the Intel SHA Extensions
+// (`sha256rnds2`/`sha256msg1`/`sha256msg2`), producing output identical to
+// the scalar compression loop. Caller must have verified `sha_ni_available()`.
+// Follows the well-known public-domain intrinsics sequence for the SHA
+// extensions (Gulley et al., "Fast SHA-256 Implementations on Intel
+// Architecture Processors").
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sha,sse4.1,ssse3")]
+pub(crate) unsafe fn transform_sha_ni(state: &mut [u32; 8], block: &[u8]) {
+    use core::arch::x86_64::*;
+
+    let mask = _mm_set_epi64x(0x0c0d0e0f08090a0bu64 as i64, 0x0405060700010203u64 as i64);
+
+    let mut tmp = _mm_loadu_si128(state.as_ptr() as *const __m128i);
+    let mut state1 = _mm_loadu_si128(state.as_ptr().add(4) as *const __m128i);
+
+    tmp = _mm_shuffle_epi32(tmp, 0xB1); // CDAB
+    state1 = _mm_shuffle_epi32(state1, 0x1B); // EFGH
+    let mut state0 = _mm_alignr_epi8(tmp, state1, 8); // ABEF
+    state1 = _mm_blend_epi16(state1, tmp, 0xF0); // CDGH
+
+    let abef_save = state0;
+    let cdgh_save = state1;
+
+    let mut msg0 = _mm_shuffle_epi8(_mm_loadu_si128(block.as_ptr() as *const __m128i), mask);
+    let mut msg1 = _mm_shuffle_epi8(
+        _mm_loadu_si128(block.as_ptr().add(16) as *const __m128i),
+        mask,
+    );
+    let mut msg2 = _mm_shuffle_epi8(
+        _mm_loadu_si128(block.as_ptr().add(32) as *const __m128i),
+        mask,
+    );
+    let mut msg3 = _mm_shuffle_epi8(
+        _mm_loadu_si128(block.as_ptr().add(48) as *const __m128i),
+        mask,
+    );
+
+    // Rounds 0-3
+    let mut msg = _mm_add_epi32(msg0, k_quad(0));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+
+    // Rounds 4-7
+    msg = _mm_add_epi32(msg1, k_quad(4));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg0 = _mm_sha256msg1_epu32(msg0, msg1);
+
+    // Rounds 8-11
+    msg = _mm_add_epi32(msg2, k_quad(8));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg1 = _mm_sha256msg1_epu32(msg1, msg2);
+
+    // Rounds 12-15
+    msg = _mm_add_epi32(msg3, k_quad(12));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg3, msg2, 4);
+    msg0 = _mm_add_epi32(msg0, tmp);
+    msg0 = _mm_sha256msg2_epu32(msg0, msg3);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg2 = _mm_sha256msg1_epu32(msg2, msg3);
+
+    // Rounds 16-19
+    msg = _mm_add_epi32(msg0, k_quad(16));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg0, msg3, 4);
+    msg1 = _mm_add_epi32(msg1, tmp);
+    msg1 = _mm_sha256msg2_epu32(msg1, msg0);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg3 = _mm_sha256msg1_epu32(msg3, msg0);
+
+    // Rounds 20-23
+    msg = _mm_add_epi32(msg1, k_quad(20));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg1, msg0, 4);
+    msg2 = _mm_add_epi32(msg2, tmp);
+    msg2 = _mm_sha256msg2_epu32(msg2, msg1);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg0 = _mm_sha256msg1_epu32(msg0, msg1);
+
+    // Rounds 24-27
+    msg = _mm_add_epi32(msg2, k_quad(24));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg2, msg1, 4);
+    msg3 = _mm_add_epi32(msg3, tmp);
+    msg3 = _mm_sha256msg2_epu32(msg3, msg2);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg1 = _mm_sha256msg1_epu32(msg1, msg2);
+
+    // Rounds 28-31
+    msg = _mm_add_epi32(msg3, k_quad(28));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg3, msg2, 4);
+    msg0 = _mm_add_epi32(msg0, tmp);
+    msg0 = _mm_sha256msg2_epu32(msg0, msg3);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg2 = _mm_sha256msg1_epu32(msg2, msg3);
+
+    // Rounds 32-35
+    msg = _mm_add_epi32(msg0, k_quad(32));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg0, msg3, 4);
+    msg1 = _mm_add_epi32(msg1, tmp);
+    msg1 = _mm_sha256msg2_epu32(msg1, msg0);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg3 = _mm_sha256msg1_epu32(msg3, msg0);
+
+    // Rounds 36-39
+    msg = _mm_add_epi32(msg1, k_quad(36));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg1, msg0, 4);
+    msg2 = _mm_add_epi32(msg2, tmp);
+    msg2 = _mm_sha256msg2_epu32(msg2, msg1);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg0 = _mm_sha256msg1_epu32(msg0, msg1);
+
+    // Rounds 40-43
+    msg = _mm_add_epi32(msg2, k_quad(40));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg2, msg1, 4);
+    msg3 = _mm_add_epi32(msg3, tmp);
+    msg3 = _mm_sha256msg2_epu32(msg3, msg2);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg1 = _mm_sha256msg1_epu32(msg1, msg2);
+
+    // Rounds 44-47
+    msg = _mm_add_epi32(msg3, k_quad(44));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg3, msg2, 4);
+    msg0 = _mm_add_epi32(msg0, tmp);
+    msg0 = _mm_sha256msg2_epu32(msg0, msg3);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg2 = _mm_sha256msg1_epu32(msg2, msg3);
+
+    // Rounds 48-51
+    msg = _mm_add_epi32(msg0, k_quad(48));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg0, msg3, 4);
+    msg1 = _mm_add_epi32(msg1, tmp);
+    msg1 = _mm_sha256msg2_epu32(msg1, msg0);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+    msg3 = _mm_sha256msg1_epu32(msg3, msg0);
+
+    // Rounds 52-55
+    msg = _mm_add_epi32(msg1, k_quad(52));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg1, msg0, 4);
+    msg2 = _mm_add_epi32(msg2, tmp);
+    msg2 = _mm_sha256msg2_epu32(msg2, msg1);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+
+    // Rounds 56-59
+    msg = _mm_add_epi32(msg2, k_quad(56));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    tmp = _mm_alignr_epi8(msg2, msg1, 4);
+    msg3 = _mm_add_epi32(msg3, tmp);
+    msg3 = _mm_sha256msg2_epu32(msg3, msg2);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+
+    // Rounds 60-63
+    msg = _mm_add_epi32(msg3, k_quad(60));
+    state1 = _mm_sha256rnds2_epu32(state1, state0, msg);
+    msg = _mm_shuffle_epi32(msg, 0x0E);
+    state0 = _mm_sha256rnds2_epu32(state0, state1, msg);
+
+    // Combine state
+    state0 = _mm_add_epi32(state0, abef_save);
+    state1 = _mm_add_epi32(state1, cdgh_save);
+
+    // Unshuffle and store back into `state` (a,b,c,d,e,f,g,h order)
+    tmp = _mm_shuffle_epi32(state0, 0x1B); // FEBA
+    state1 = _mm_shuffle_epi32(state1, 0xB1); // DCHG
+    state0 = _mm_blend_epi16(tmp, state1, 0xF0); // DCBA
+    state1 = _mm_alignr_epi8(state1, tmp, 8); // HGFE
+
+    _mm_storeu_si128(state.as_mut_ptr() as *mut __m128i, state0);
+    _mm_storeu_si128(state.as_mut_ptr().add(4) as *mut __m128i, state1);
+}
+
+// AArch64 path using the ARMv8 SHA2 crypto extensions. Unlike x86 CPUID,
+// there is no OS-independent way to probe AArch64 feature bits from
+// `no_std` code, so this path is gated at compile time: build with
+// `RUSTFLAGS="-C target-feature=+sha2"` (or a `target-cpu` that implies
+// it) to enable it, otherwise the scalar `transform` is used unconditionally.
+#[cfg(all(target_arch = "aarch64", target_feature = "sha2"))]
+#[target_feature(enable = "sha2,neon")]
+pub(crate) unsafe fn transform_sha2_neon(state: &mut [u32; 8], block: &[u8]) {
+    use core::arch::aarch64::*;
+
+    let mut state0 = vld1q_u32(state.as_ptr());
+    let mut state1 = vld1q_u32(state.as_ptr().add(4));
+    let abef_save = state0;
+    let cdgh_save = state1;
+
+    let rev = |v: uint32x4_t| vreinterpretq_u32_u8(vrev32q_u8(vreinterpretq_u8_u32(v)));
+
+    let mut msg0 = rev(vld1q_u32(block.as_ptr() as *const u32));
+    let mut msg1 = rev(vld1q_u32(block.as_ptr().add(16) as *const u32));
+    let mut msg2 = rev(vld1q_u32(block.as_ptr().add(32) as *const u32));
+    let mut msg3 = rev(vld1q_u32(block.as_ptr().add(48) as *const u32));
+
+    for group in 0..16 {
+        let k0 = group * 4;
+        let wk = vaddq_u32(msg0, vld1q_u32(K[k0..].as_ptr()));
+        let save = state0;
+        state0 = vsha256hq_u32(state0, state1, wk);
+        state1 = vsha256h2q_u32(state1, save, wk);
+
+        if group < 12 {
+            msg0 = vsha256su1q_u32(vsha256su0q_u32(msg0, msg1), msg2, msg3);
+        }
+
+        // Rotate the message registers: msg0 retires, msg1..msg3 shift down
+        // and the newly-extended schedule word becomes the new msg3.
+        let new_msg3 = msg0;
+        msg0 = msg1;
+        msg1 = msg2;
+        msg2 = msg3;
+        msg3 = new_msg3;
+    }
+
+    state0 = vaddq_u32(state0, abef_save);
+    state1 = vaddq_u32(state1, cdgh_save);
+
+    vst1q_u32(state.as_mut_ptr(), state0);
+    vst1q_u32(state.as_mut_ptr().add(4), state1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA256_IV: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // FIPS 180-4 one-block KAT: "abc", padded to a single 64-byte block.
+    const ABC_BLOCK: [u8; 64] = [
+        0x61, 0x62, 0x63, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0x18,
+    ];
+    const ABC_DIGEST: [u32; 8] = [
+        0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+        0xf20015ad,
+    ];
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn sha_ni_matches_fips_kat() {
+        if !sha_ni_available() {
+            return;
+        }
+        let mut state = SHA256_IV;
+        unsafe { transform_sha_ni(&mut state, &ABC_BLOCK) };
+        assert_eq!(state, ABC_DIGEST);
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "sha2"))]
+    #[test]
+    fn neon_matches_fips_kat() {
+        let mut state = SHA256_IV;
+        unsafe { transform_sha2_neon(&mut state, &ABC_BLOCK) };
+        assert_eq!(state, ABC_DIGEST);
+    }
+}
@@ -2,6 +2,13 @@
 
 #![no_std]
 
+mod hwaccel;
+mod multilane;
+mod safe;
+mod sha512;
+
+pub use safe::Sha256;
+
 // Rotate right operation
 #[inline]
 fn rotr(x: u32, n: u32) -> u32 {
@@ -40,7 +47,7 @@ fn ssig1(x: u32) -> u32 {
 }
 
 // Round constants
-const K: [u32; 64] = [
+pub(crate) const K: [u32; 64] = [
     0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5,
     0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
     0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3,
@@ -60,6 +67,7 @@ const K: [u32; 64] = [
 ];
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Sha256Ctx {
     h: [u32; 8],
     buffer: [u8; 64],
@@ -69,6 +77,22 @@ pub struct Sha256Ctx {
 
 impl Sha256Ctx {
     fn transform(&mut self, block: &[u8]) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if hwaccel::sha_ni_available() {
+                unsafe { hwaccel::transform_sha_ni(&mut self.h, block) };
+                return;
+            }
+        }
+        #[cfg(all(target_arch = "aarch64", target_feature = "sha2"))]
+        {
+            unsafe { hwaccel::transform_sha2_neon(&mut self.h, block) };
+            return;
+        }
+        self.transform_scalar(block);
+    }
+
+    fn transform_scalar(&mut self, block: &[u8]) {
         let mut w = [0u32; 64];
         
         // Prepare message schedule
@@ -140,6 +164,21 @@ pub extern "C" fn rust_sha256_init(ctx: *mut Sha256Ctx) {
     }
 }
 
+// SHA-224 is SHA-256 with a different IV and a truncated, 7-word digest.
+// It reuses `Sha256Ctx`, `transform`, `rust_sha256_update` and the padding
+// logic in `rust_sha256_final` unchanged.
+#[no_mangle]
+pub extern "C" fn rust_sha224_init(ctx: *mut Sha256Ctx) {
+    unsafe {
+        (*ctx).h = [
+            0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939,
+            0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+        ];
+        (*ctx).buflen = 0;
+        (*ctx).bitlen = 0;
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn rust_sha256_update(ctx: *mut Sha256Ctx, data: *const u8, len: u32) {
     unsafe {
@@ -243,6 +282,18 @@ pub extern "C" fn rust_sha256_final(ctx: *mut Sha256Ctx, out_hash32: *mut u8) {
     }
 }
 
+// Identical to `rust_sha256_final` except only the first 7 state words
+// (28 bytes) are emitted, per FIPS 180-4's SHA-224 truncation of SHA-256.
+#[no_mangle]
+pub extern "C" fn rust_sha224_final(ctx: *mut Sha256Ctx, out_hash28: *mut u8) {
+    let mut full = [0u8; 32];
+    rust_sha256_final(ctx, full.as_mut_ptr());
+    unsafe {
+        let out_slice = core::slice::from_raw_parts_mut(out_hash28, 28);
+        out_slice.copy_from_slice(&full[..28]);
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn rust_sha256_to_hex(hash32: *const u8, hex_out: *mut u8) {
     const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
@@ -259,7 +310,231 @@ pub extern "C" fn rust_sha256_to_hex(hash32: *const u8, hex_out: *mut u8) {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn rust_sha224_to_hex(hash28: *const u8, hex_out: *mut u8) {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+    unsafe {
+        let hash_slice = core::slice::from_raw_parts(hash28, 28);
+        let hex_slice = core::slice::from_raw_parts_mut(hex_out, 57);
+
+        for i in 0..28 {
+            let b = hash_slice[i];
+            hex_slice[i * 2] = HEX_CHARS[(b >> 4) as usize];
+            hex_slice[i * 2 + 1] = HEX_CHARS[(b & 0x0F) as usize];
+        }
+        hex_slice[56] = 0; // null terminator
+    }
+}
+
+// Hashes a single 32-byte message with the standard SHA-256 IV. The
+// padded block is fixed (32 bytes of data, one 0x80 byte, 23 zero bytes,
+// then the 64-bit length field holding 256), so it is built directly instead of going
+// through `rust_sha256_update`/`rust_sha256_final`.
+fn sha256_of_32_bytes(data: &[u8; 32]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+        0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    let mut block = [0u8; 64];
+    block[..32].copy_from_slice(data);
+    block[32] = 0x80;
+    // 32 bytes = 256 bits = 0x100, written big-endian into the 8-byte
+    // length field at block[56..64].
+    block[62] = 0x01;
+
+    let mut ctx = Sha256Ctx {
+        h,
+        buffer: [0u8; 64],
+        buflen: 0,
+        bitlen: 0,
+    };
+    ctx.transform(&block);
+    h = ctx.h;
+
+    let mut out = [0u8; 32];
+    for i in 0..8 {
+        let val = h[i];
+        out[i * 4] = (val >> 24) as u8;
+        out[i * 4 + 1] = (val >> 16) as u8;
+        out[i * 4 + 2] = (val >> 8) as u8;
+        out[i * 4 + 3] = val as u8;
+    }
+    out
+}
+
+// Finishes the current message into a 32-byte digest, then hashes that
+// digest again (SHA256(SHA256(msg))) as used by Bitcoin and related
+// protocols. Avoids round-tripping through FFI and an intermediate buffer.
+#[no_mangle]
+pub extern "C" fn rust_sha256d_final(ctx: *mut Sha256Ctx, out_hash32: *mut u8) {
+    let mut inner_hash = [0u8; 32];
+    rust_sha256_final(ctx, inner_hash.as_mut_ptr());
+
+    let outer_hash = sha256_of_32_bytes(&inner_hash);
+    unsafe {
+        let out_slice = core::slice::from_raw_parts_mut(out_hash32, 32);
+        out_slice.copy_from_slice(&outer_hash);
+    }
+}
+
+// One-shot sha256d over an arbitrary-length message.
+#[no_mangle]
+pub extern "C" fn rust_sha256d(data: *const u8, len: u32, out_hash32: *mut u8) {
+    let mut ctx = core::mem::MaybeUninit::<Sha256Ctx>::uninit();
+    let ctx_ptr = ctx.as_mut_ptr();
+    rust_sha256_init(ctx_ptr);
+    rust_sha256_update(ctx_ptr, data, len);
+    rust_sha256d_final(ctx_ptr, out_hash32);
+}
+
+// Serialized size of a `Sha256Ctx` snapshot: 8 big-endian `h` words (32
+// bytes), an 8-byte big-endian `bitlen`, a 4-byte big-endian `buflen`,
+// and the full 64-byte `buffer` (including its unused tail), so a state
+// can be exported and resumed from any point, not just block boundaries.
+pub const SHA256_STATE_LEN: usize = 32 + 8 + 4 + 64;
+
+// Snapshots a `Sha256Ctx` so it can be serialized (e.g. cached alongside a
+// fixed prefix like a block header template) and later restored with
+// `rust_sha256_import_state` to resume hashing the tail.
+#[no_mangle]
+pub extern "C" fn rust_sha256_export_state(ctx: *const Sha256Ctx, out_state: *mut u8) {
+    unsafe {
+        let ctx_ref = &*ctx;
+        let out = core::slice::from_raw_parts_mut(out_state, SHA256_STATE_LEN);
+
+        for i in 0..8 {
+            let val = ctx_ref.h[i];
+            out[i * 4] = (val >> 24) as u8;
+            out[i * 4 + 1] = (val >> 16) as u8;
+            out[i * 4 + 2] = (val >> 8) as u8;
+            out[i * 4 + 3] = val as u8;
+        }
+
+        let bits = ctx_ref.bitlen;
+        for i in 0..8 {
+            out[32 + i] = (bits >> (56 - i * 8)) as u8;
+        }
+
+        let buflen = ctx_ref.buflen;
+        out[40] = (buflen >> 24) as u8;
+        out[41] = (buflen >> 16) as u8;
+        out[42] = (buflen >> 8) as u8;
+        out[43] = buflen as u8;
+
+        out[44..44 + 64].copy_from_slice(&ctx_ref.buffer);
+    }
+}
+
+// Reconstructs a `Sha256Ctx` from a blob written by
+// `rust_sha256_export_state`, ready to continue with `rust_sha256_update`.
+#[no_mangle]
+pub extern "C" fn rust_sha256_import_state(ctx: *mut Sha256Ctx, in_state: *const u8) {
+    unsafe {
+        let ctx_ref = &mut *ctx;
+        let state = core::slice::from_raw_parts(in_state, SHA256_STATE_LEN);
+
+        for i in 0..8 {
+            let j = i * 4;
+            ctx_ref.h[i] = ((state[j] as u32) << 24)
+                | ((state[j + 1] as u32) << 16)
+                | ((state[j + 2] as u32) << 8)
+                | (state[j + 3] as u32);
+        }
+
+        let mut bits: u64 = 0;
+        for i in 0..8 {
+            bits = (bits << 8) | (state[32 + i] as u64);
+        }
+        ctx_ref.bitlen = bits;
+
+        ctx_ref.buflen = ((state[40] as u32) << 24)
+            | ((state[41] as u32) << 16)
+            | ((state[42] as u32) << 8)
+            | (state[43] as u32);
+
+        ctx_ref.buffer.copy_from_slice(&state[44..44 + 64]);
+    }
+}
+
+// Omitted under `cfg(test)`: the test harness links against `std`, which
+// already provides a `panic_impl`, and defining a second one is a hard
+// compile error (E0152).
+#[cfg(not(test))]
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     loop {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_ctx() -> Sha256Ctx {
+        Sha256Ctx { h: [0; 8], buffer: [0; 64], buflen: 0, bitlen: 0 }
+    }
+
+    // FIPS 180-4 KAT: SHA-224("abc").
+    #[test]
+    fn sha224_matches_fips_kat() {
+        let mut ctx = new_ctx();
+        rust_sha224_init(&mut ctx);
+        rust_sha256_update(&mut ctx, b"abc".as_ptr(), 3);
+        let mut out = [0u8; 28];
+        rust_sha224_final(&mut ctx, out.as_mut_ptr());
+        assert_eq!(
+            out,
+            [
+                0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22, 0x86, 0x42, 0xa4, 0x77, 0xbd,
+                0xa2, 0x55, 0xb3, 0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7, 0xe3, 0x6c,
+                0x9d, 0xa7,
+            ]
+        );
+    }
+
+    // sha256d("abc") == SHA-256(SHA-256("abc")), cross-checked against
+    // Python's hashlib; this is the digest the length-offset bug fixed in
+    // an earlier commit would have broken.
+    #[test]
+    fn sha256d_matches_double_sha256() {
+        let mut out = [0u8; 32];
+        rust_sha256d(b"abc".as_ptr(), 3, out.as_mut_ptr());
+        assert_eq!(
+            out,
+            [
+                0x4f, 0x8b, 0x42, 0xc2, 0x2d, 0xd3, 0x72, 0x9b, 0x51, 0x9b, 0xa6, 0xf6, 0x8d,
+                0x2d, 0xa7, 0xcc, 0x5b, 0x2d, 0x60, 0x6d, 0x05, 0xda, 0xed, 0x5a, 0xd5, 0x12,
+                0x8c, 0xc0, 0x3e, 0x6c, 0x63, 0x58,
+            ]
+        );
+    }
+
+    // Exporting mid-stream and importing into a fresh context should
+    // resume hashing exactly where it left off, producing the same digest
+    // as hashing the whole message without pausing.
+    #[test]
+    fn export_import_state_resumes_hashing() {
+        let msg = b"hello world";
+
+        let mut direct = new_ctx();
+        rust_sha256_init(&mut direct);
+        rust_sha256_update(&mut direct, msg.as_ptr(), msg.len() as u32);
+        let mut direct_digest = [0u8; 32];
+        rust_sha256_final(&mut direct, direct_digest.as_mut_ptr());
+
+        let mut paused = new_ctx();
+        rust_sha256_init(&mut paused);
+        rust_sha256_update(&mut paused, msg.as_ptr(), 6); // "hello "
+
+        let mut state = [0u8; SHA256_STATE_LEN];
+        rust_sha256_export_state(&paused, state.as_mut_ptr());
+
+        let mut resumed = new_ctx();
+        rust_sha256_import_state(&mut resumed, state.as_ptr());
+        rust_sha256_update(&mut resumed, msg[6..].as_ptr(), (msg.len() - 6) as u32);
+        let mut resumed_digest = [0u8; 32];
+        rust_sha256_final(&mut resumed, resumed_digest.as_mut_ptr());
+
+        assert_eq!(resumed_digest, direct_digest);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+// Safe, idiomatic wrapper around the raw `extern "C"` SHA-256 API. Wraps
+// `Sha256Ctx` and the existing `rust_sha256_*` functions without exposing
+// any raw pointers, so downstream Rust crates can hash data without
+// writing `unsafe`; C callers keep using the FFI functions directly, and
+// the ABI those expose is unchanged.
+
+use crate::{rust_sha256_final, rust_sha256_init, rust_sha256_to_hex, rust_sha256_update, Sha256Ctx};
+use core::hash::Hasher;
+
+#[derive(Clone)]
+pub struct Sha256 {
+    ctx: Sha256Ctx,
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        // `rust_sha256_init` only sets `h`, `buflen` and `bitlen`; `buffer`
+        // is zeroed explicitly here rather than left uninitialized, since
+        // nothing (re-)initializes it otherwise.
+        let mut ctx = Sha256Ctx {
+            h: [0; 8],
+            buffer: [0u8; 64],
+            buflen: 0,
+            bitlen: 0,
+        };
+        rust_sha256_init(&mut ctx);
+        Sha256 { ctx }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        rust_sha256_update(&mut self.ctx, data.as_ptr(), data.len() as u32);
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        rust_sha256_final(&mut self.ctx, out.as_mut_ptr());
+        out
+    }
+
+    pub fn finalize_hex(self) -> [u8; 64] {
+        let digest = self.finalize();
+        let mut hex_with_nul = [0u8; 65];
+        rust_sha256_to_hex(digest.as_ptr(), hex_with_nul.as_mut_ptr());
+        let mut out = [0u8; 64];
+        out.copy_from_slice(&hex_with_nul[..64]);
+        out
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Lets `Sha256` stand in for `core::hash::Hash`-based APIs that expect a
+// `Hasher`. `finish` has to borrow `self` immutably, so it hashes a clone
+// rather than consuming the original, then returns the digest's first 8
+// bytes as a big-endian u64.
+impl Hasher for Sha256 {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.clone().finalize();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+}
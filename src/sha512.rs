@@ -0,0 +1,377 @@
+// Bare-metal SHA-512/SHA-384 implementation, mirroring `Sha256Ctx` in
+// lib.rs but operating on 128-byte blocks with a 64-bit word size and an
+// 80-round schedule, per FIPS 180-4.
+
+#[inline]
+fn rotr(x: u64, n: u32) -> u64 {
+    (x >> n) | (x << (64 - n))
+}
+
+#[inline]
+fn ch(x: u64, y: u64, z: u64) -> u64 {
+    (x & y) ^ (!x & z)
+}
+
+#[inline]
+fn maj(x: u64, y: u64, z: u64) -> u64 {
+    (x & y) ^ (x & z) ^ (y & z)
+}
+
+#[inline]
+fn bsig0(x: u64) -> u64 {
+    rotr(x, 28) ^ rotr(x, 34) ^ rotr(x, 39)
+}
+
+#[inline]
+fn bsig1(x: u64) -> u64 {
+    rotr(x, 14) ^ rotr(x, 18) ^ rotr(x, 41)
+}
+
+#[inline]
+fn ssig0(x: u64) -> u64 {
+    rotr(x, 1) ^ rotr(x, 8) ^ (x >> 7)
+}
+
+#[inline]
+fn ssig1(x: u64) -> u64 {
+    rotr(x, 19) ^ rotr(x, 61) ^ (x >> 6)
+}
+
+// Round constants
+const K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+
+#[repr(C)]
+pub struct Sha512Ctx {
+    h: [u64; 8],
+    buffer: [u8; 128],
+    buflen: u32,
+    // Bits processed so far. The length field in the padding is 128 bits
+    // wide; the high 64 bits are always written as zero, which holds for
+    // any message shorter than 2^64 bits (2 exbibytes).
+    bitlen: u64,
+}
+
+impl Sha512Ctx {
+    fn transform(&mut self, block: &[u8]) {
+        let mut w = [0u64; 80];
+
+        // Prepare message schedule
+        for i in 0..16 {
+            let j = i * 8;
+            w[i] = ((block[j] as u64) << 56)
+                | ((block[j + 1] as u64) << 48)
+                | ((block[j + 2] as u64) << 40)
+                | ((block[j + 3] as u64) << 32)
+                | ((block[j + 4] as u64) << 24)
+                | ((block[j + 5] as u64) << 16)
+                | ((block[j + 6] as u64) << 8)
+                | (block[j + 7] as u64);
+        }
+
+        for i in 16..80 {
+            w[i] = ssig1(w[i - 2])
+                .wrapping_add(w[i - 7])
+                .wrapping_add(ssig0(w[i - 15]))
+                .wrapping_add(w[i - 16]);
+        }
+
+        // Initialize working variables
+        let mut a = self.h[0];
+        let mut b = self.h[1];
+        let mut c = self.h[2];
+        let mut d = self.h[3];
+        let mut e = self.h[4];
+        let mut f = self.h[5];
+        let mut g = self.h[6];
+        let mut h = self.h[7];
+
+        // Main compression loop
+        for i in 0..80 {
+            let t1 = h
+                .wrapping_add(bsig1(e))
+                .wrapping_add(ch(e, f, g))
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let t2 = bsig0(a).wrapping_add(maj(a, b, c));
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        // Add to state
+        self.h[0] = self.h[0].wrapping_add(a);
+        self.h[1] = self.h[1].wrapping_add(b);
+        self.h[2] = self.h[2].wrapping_add(c);
+        self.h[3] = self.h[3].wrapping_add(d);
+        self.h[4] = self.h[4].wrapping_add(e);
+        self.h[5] = self.h[5].wrapping_add(f);
+        self.h[6] = self.h[6].wrapping_add(g);
+        self.h[7] = self.h[7].wrapping_add(h);
+    }
+}
+
+fn init(ctx: *mut Sha512Ctx, iv: [u64; 8]) {
+    unsafe {
+        (*ctx).h = iv;
+        (*ctx).buflen = 0;
+        (*ctx).bitlen = 0;
+    }
+}
+
+fn update(ctx: *mut Sha512Ctx, data: *const u8, len: u32) {
+    unsafe {
+        let ctx_ref = &mut *ctx;
+        let data_slice = core::slice::from_raw_parts(data, len as usize);
+
+        ctx_ref.bitlen += (len as u64) * 8;
+
+        let mut i = 0;
+
+        // Fill buffer if partially full
+        if ctx_ref.buflen > 0 {
+            let need = 128 - ctx_ref.buflen;
+            if len < need {
+                for j in 0..len {
+                    ctx_ref.buffer[(ctx_ref.buflen + j) as usize] = data_slice[j as usize];
+                }
+                ctx_ref.buflen += len;
+                return;
+            } else {
+                for j in 0..need {
+                    ctx_ref.buffer[(ctx_ref.buflen + j) as usize] = data_slice[j as usize];
+                }
+                let mut temp_buffer = [0u8; 128];
+                temp_buffer.copy_from_slice(&ctx_ref.buffer);
+                ctx_ref.transform(&temp_buffer);
+                ctx_ref.buflen = 0;
+                i += need;
+            }
+        }
+
+        // Process full blocks
+        while i + 128 <= len {
+            ctx_ref.transform(&data_slice[i as usize..(i + 128) as usize]);
+            i += 128;
+        }
+
+        // Copy remainder to buffer
+        if i < len {
+            let rem = len - i;
+            for j in 0..rem {
+                ctx_ref.buffer[j as usize] = data_slice[(i + j) as usize];
+            }
+            ctx_ref.buflen = rem;
+        }
+    }
+}
+
+// Pads and transforms the final block(s), leaving the digest in `ctx.h`.
+fn finalize(ctx: *mut Sha512Ctx) {
+    unsafe {
+        let ctx_ref = &mut *ctx;
+        let bits = ctx_ref.bitlen;
+
+        // Append 0x80
+        ctx_ref.buffer[ctx_ref.buflen as usize] = 0x80;
+        ctx_ref.buflen += 1;
+
+        // Handle case where padding doesn't fit
+        if ctx_ref.buflen > 112 {
+            while ctx_ref.buflen < 128 {
+                ctx_ref.buffer[ctx_ref.buflen as usize] = 0;
+                ctx_ref.buflen += 1;
+            }
+            let mut temp_buffer = [0u8; 128];
+            temp_buffer.copy_from_slice(&ctx_ref.buffer);
+            ctx_ref.transform(&temp_buffer);
+            ctx_ref.buflen = 0;
+        }
+
+        // Pad with zeros
+        while ctx_ref.buflen < 112 {
+            ctx_ref.buffer[ctx_ref.buflen as usize] = 0;
+            ctx_ref.buflen += 1;
+        }
+
+        // Append 128-bit length (high 64 bits are always zero; see `bitlen`)
+        for i in 0..8 {
+            ctx_ref.buffer[112 + i] = 0;
+        }
+        ctx_ref.buffer[120] = (bits >> 56) as u8;
+        ctx_ref.buffer[121] = (bits >> 48) as u8;
+        ctx_ref.buffer[122] = (bits >> 40) as u8;
+        ctx_ref.buffer[123] = (bits >> 32) as u8;
+        ctx_ref.buffer[124] = (bits >> 24) as u8;
+        ctx_ref.buffer[125] = (bits >> 16) as u8;
+        ctx_ref.buffer[126] = (bits >> 8) as u8;
+        ctx_ref.buffer[127] = bits as u8;
+
+        // Final transform
+        let mut temp_buffer = [0u8; 128];
+        temp_buffer.copy_from_slice(&ctx_ref.buffer);
+        ctx_ref.transform(&temp_buffer);
+    }
+}
+
+fn write_digest(h: &[u64], out: *mut u8, words: usize) {
+    unsafe {
+        let out_slice = core::slice::from_raw_parts_mut(out, words * 8);
+        for i in 0..words {
+            let val = h[i];
+            out_slice[i * 8] = (val >> 56) as u8;
+            out_slice[i * 8 + 1] = (val >> 48) as u8;
+            out_slice[i * 8 + 2] = (val >> 40) as u8;
+            out_slice[i * 8 + 3] = (val >> 32) as u8;
+            out_slice[i * 8 + 4] = (val >> 24) as u8;
+            out_slice[i * 8 + 5] = (val >> 16) as u8;
+            out_slice[i * 8 + 6] = (val >> 8) as u8;
+            out_slice[i * 8 + 7] = val as u8;
+        }
+    }
+}
+
+fn to_hex(hash: *const u8, hex_out: *mut u8, bytes: usize) {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+    unsafe {
+        let hash_slice = core::slice::from_raw_parts(hash, bytes);
+        let hex_slice = core::slice::from_raw_parts_mut(hex_out, bytes * 2 + 1);
+
+        for i in 0..bytes {
+            let b = hash_slice[i];
+            hex_slice[i * 2] = HEX_CHARS[(b >> 4) as usize];
+            hex_slice[i * 2 + 1] = HEX_CHARS[(b & 0x0F) as usize];
+        }
+        hex_slice[bytes * 2] = 0; // null terminator
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha512_init(ctx: *mut Sha512Ctx) {
+    init(ctx, SHA512_IV);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha512_update(ctx: *mut Sha512Ctx, data: *const u8, len: u32) {
+    update(ctx, data, len);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha512_final(ctx: *mut Sha512Ctx, out_hash64: *mut u8) {
+    finalize(ctx);
+    let ctx_ref = unsafe { &*ctx };
+    write_digest(&ctx_ref.h, out_hash64, 8);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha512_to_hex(hash64: *const u8, hex_out: *mut u8) {
+    to_hex(hash64, hex_out, 64);
+}
+
+// SHA-384 reuses the SHA-512 engine unchanged; only the IV and the
+// truncated 48-byte output differ.
+#[no_mangle]
+pub extern "C" fn rust_sha384_init(ctx: *mut Sha512Ctx) {
+    init(ctx, SHA384_IV);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha384_update(ctx: *mut Sha512Ctx, data: *const u8, len: u32) {
+    update(ctx, data, len);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha384_final(ctx: *mut Sha512Ctx, out_hash48: *mut u8) {
+    finalize(ctx);
+    let ctx_ref = unsafe { &*ctx };
+    write_digest(&ctx_ref.h, out_hash48, 6);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_sha384_to_hex(hash48: *const u8, hex_out: *mut u8) {
+    to_hex(hash48, hex_out, 48);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_ctx() -> Sha512Ctx {
+        Sha512Ctx { h: [0; 8], buffer: [0; 128], buflen: 0, bitlen: 0 }
+    }
+
+    // FIPS 180-4 KAT: SHA-512("abc").
+    #[test]
+    fn sha512_matches_fips_kat() {
+        let mut ctx = new_ctx();
+        rust_sha512_init(&mut ctx);
+        rust_sha512_update(&mut ctx, b"abc".as_ptr(), 3);
+        let mut out = [0u8; 64];
+        rust_sha512_final(&mut ctx, out.as_mut_ptr());
+        assert_eq!(
+            out,
+            [
+                0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae,
+                0x20, 0x41, 0x31, 0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e,
+                0xee, 0xe6, 0x4b, 0x55, 0xd3, 0x9a, 0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1,
+                0xa8, 0x36, 0xba, 0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd, 0x45, 0x4d, 0x44, 0x23,
+                0x64, 0x3c, 0xe8, 0x0e, 0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f,
+            ]
+        );
+    }
+
+    // FIPS 180-4 KAT: SHA-384("abc").
+    #[test]
+    fn sha384_matches_fips_kat() {
+        let mut ctx = new_ctx();
+        rust_sha384_init(&mut ctx);
+        rust_sha384_update(&mut ctx, b"abc".as_ptr(), 3);
+        let mut out = [0u8; 48];
+        rust_sha384_final(&mut ctx, out.as_mut_ptr());
+        assert_eq!(
+            out,
+            [
+                0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, 0xb5, 0xa0, 0x3d, 0x69, 0x9a,
+                0xc6, 0x50, 0x07, 0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63, 0x1a, 0x8b,
+                0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed, 0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc,
+                0x23, 0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7,
+            ]
+        );
+    }
+}